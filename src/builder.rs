@@ -0,0 +1,301 @@
+use crate::{Cell, Result, Spreadsheet, SpreadsheetError};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+#[cfg(feature = "gzip")]
+use std::io::Read;
+
+/// Controls which fields get surrounding whitespace stripped before type
+/// inference.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Trim {
+    /// Don't trim anything.
+    None,
+    /// Trim only the header row.
+    Headers,
+    /// Trim only data fields.
+    Fields,
+    /// Trim both the header row and data fields.
+    All,
+}
+
+/// Configures how a [`Spreadsheet`] is parsed from a delimited source,
+/// mirroring the builder pattern used by the `csv` crate. `Spreadsheet::read`
+/// is a thin wrapper over `ReaderBuilder::new(delimiter).from_path(..)` with
+/// every option at its default.
+pub struct ReaderBuilder {
+    delimiter: char,
+    quote: char,
+    trim: Trim,
+    flexible: bool,
+    has_headers: bool,
+    null_tokens: HashSet<String>,
+}
+
+impl ReaderBuilder {
+    /// Create a builder for `delimiter`-separated input with every other
+    /// option at its default: no trimming, strict (non-flexible) records,
+    /// `"` quoting, a header row present, and no null tokens besides a
+    /// literal blank field.
+    pub fn new(delimiter: char) -> ReaderBuilder {
+        ReaderBuilder {
+            delimiter,
+            quote: '"',
+            trim: Trim::None,
+            flexible: false,
+            has_headers: true,
+            null_tokens: HashSet::new(),
+        }
+    }
+
+    /// Set which fields get surrounding whitespace trimmed before type
+    /// inference.
+    pub fn trim(&mut self, trim: Trim) -> &mut ReaderBuilder {
+        self.trim = trim;
+        self
+    }
+
+    /// Allow rows with fewer or more fields than the header instead of
+    /// erroring with [`SpreadsheetError::RaggedRow`]; short rows are padded
+    /// with [`Cell::Empty`] and long rows are truncated.
+    pub fn flexible(&mut self, yes: bool) -> &mut ReaderBuilder {
+        self.flexible = yes;
+        self
+    }
+
+    /// Set the character used to quote fields (default `"`).
+    pub fn quote(&mut self, quote: char) -> &mut ReaderBuilder {
+        self.quote = quote;
+        self
+    }
+
+    /// Whether the first record is a header row. When `false`, headers are
+    /// synthesized as `col0..colN`.
+    pub fn has_headers(&mut self, yes: bool) -> &mut ReaderBuilder {
+        self.has_headers = yes;
+        self
+    }
+
+    /// Treat `token` (e.g. `"NA"`, `"NULL"`) as [`Cell::Empty`] wherever it
+    /// appears as a field, in addition to a literal blank field.
+    pub fn null_token(&mut self, token: &str) -> &mut ReaderBuilder {
+        self.null_tokens.insert(token.to_string());
+        self
+    }
+
+    /// Build a [`Spreadsheet`] by reading `filename` with the configured
+    /// options.
+    pub fn from_path(&self, filename: &str) -> Result<Spreadsheet> {
+        self.from_reader(BufReader::new(File::open(filename)?))
+    }
+
+    /// Build a [`Spreadsheet`] from any buffered reader, transparently
+    /// decompressing gzip input (sniffed from the `0x1f 0x8b` magic bytes at
+    /// the start of the stream) when built with the `gzip` feature; without
+    /// it, gzip input yields a [`SpreadsheetError::Io`].
+    pub fn from_reader<R: BufRead>(&self, mut reader: R) -> Result<Spreadsheet> {
+        let is_gzip = reader.fill_buf()?.starts_with(&[0x1f, 0x8b]);
+        let mut bytes = Vec::new();
+        if is_gzip {
+            Self::read_gzip(reader, &mut bytes)?;
+        } else {
+            reader.read_to_end(&mut bytes)?;
+        }
+        let text = std::str::from_utf8(&bytes)?;
+        self.parse(text)
+    }
+
+    #[cfg(feature = "gzip")]
+    fn read_gzip<R: BufRead>(reader: R, bytes: &mut Vec<u8>) -> Result<()> {
+        flate2::bufread::MultiGzDecoder::new(reader).read_to_end(bytes)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    fn read_gzip<R: BufRead>(_reader: R, _bytes: &mut Vec<u8>) -> Result<()> {
+        Err(SpreadsheetError::Io(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "reading a gzip-compressed file requires building with the `gzip` feature",
+        )))
+    }
+
+    fn parse(&self, text: &str) -> Result<Spreadsheet> {
+        let mut lines = text.lines();
+        let mut line_no = 0;
+
+        let trim_headers = matches!(self.trim, Trim::Headers | Trim::All);
+        let trim_data_fields = matches!(self.trim, Trim::Fields | Trim::All);
+
+        let mut headers = if self.has_headers {
+            match parse_record(&mut lines, &mut line_no, self.delimiter, self.quote)? {
+                Some((_, fields)) => trim_fields(fields, trim_headers),
+                None => Vec::new(),
+            }
+        } else {
+            Vec::new()
+        };
+
+        let mut cols = headers.len();
+        let mut data: Vec<Cell> = Vec::new();
+        let mut rows = 0;
+        while let Some((line, fields)) =
+            parse_record(&mut lines, &mut line_no, self.delimiter, self.quote)?
+        {
+            let mut fields = trim_fields(fields, trim_data_fields);
+
+            if !self.has_headers && cols == 0 {
+                cols = fields.len();
+                headers = (0..cols).map(|i| format!("col{}", i)).collect();
+            }
+
+            if fields.len() != cols {
+                if self.flexible {
+                    fields.resize(cols, String::new());
+                } else {
+                    return Err(SpreadsheetError::RaggedRow {
+                        line,
+                        expected: cols,
+                        found: fields.len(),
+                    });
+                }
+            }
+
+            for value in fields {
+                data.push(self.parse_cell(value));
+            }
+            rows += 1;
+        }
+
+        Ok(Spreadsheet {
+            headers,
+            data,
+            cols,
+            rows,
+            delimiter: self.delimiter,
+            quote: self.quote,
+        })
+    }
+
+    fn parse_cell(&self, value: String) -> Cell {
+        if value.is_empty() || self.null_tokens.contains(&value) {
+            Cell::Empty
+        } else if let Ok(x) = value.parse::<i64>() {
+            Cell::Integer(x)
+        } else if let Ok(x) = value.parse::<f64>() {
+            Cell::Float(x)
+        } else {
+            Cell::Text(value)
+        }
+    }
+}
+
+impl Default for ReaderBuilder {
+    fn default() -> ReaderBuilder {
+        ReaderBuilder::new(',')
+    }
+}
+
+/// Trim surrounding whitespace from each field that wasn't quoted, leaving
+/// quoted fields untouched so deliberately-preserved whitespace survives.
+/// No-op unless `should_trim` is set.
+fn trim_fields(fields: Fields, should_trim: bool) -> Vec<String> {
+    fields
+        .into_iter()
+        .map(|(field, quoted)| {
+            if should_trim && !quoted {
+                field.trim().to_string()
+            } else {
+                field
+            }
+        })
+        .collect()
+}
+
+/// A record's fields, each paired with whether it was quoted in the source.
+type Fields = Vec<(String, bool)>;
+
+/// Pull one RFC 4180 record's worth of fields from a stream of physical
+/// lines, advancing `line_no` (1-indexed) past every physical line
+/// consumed. A field quoted with `quote` may embed `delimiter` or a line
+/// break, in which case further lines are pulled and joined with `\n`
+/// until the quotes in the record balance. Returns `Ok(None)` once `lines`
+/// is exhausted. On success, yields the starting line number of the record
+/// alongside its fields.
+fn parse_record<'a, I: Iterator<Item = &'a str>>(
+    lines: &mut I,
+    line_no: &mut usize,
+    delimiter: char,
+    quote: char,
+) -> Result<Option<(usize, Fields)>> {
+    let start = *line_no + 1;
+    let mut line = match lines.next() {
+        Some(line) => {
+            *line_no += 1;
+            line.to_string()
+        }
+        None => return Ok(None),
+    };
+
+    loop {
+        match split_record(&line, delimiter, quote) {
+            Some(fields) => return Ok(Some((start, fields))),
+            None => match lines.next() {
+                Some(next) => {
+                    *line_no += 1;
+                    line.push('\n');
+                    line.push_str(next);
+                }
+                None => {
+                    return Err(SpreadsheetError::Io(std::io::Error::from(
+                        std::io::ErrorKind::UnexpectedEof,
+                    )))
+                }
+            },
+        }
+    }
+}
+
+/// Split a single (possibly already-joined) line into fields, honoring
+/// `quote`-quoting: a doubled quote inside a quoted field decodes to a
+/// single quote, and a delimiter inside quotes is part of the value. Each
+/// field is paired with whether it was quoted, so callers can tell
+/// deliberately-quoted whitespace apart from bare whitespace. Returns
+/// `None` if the line ends with an unbalanced quote, meaning the record
+/// isn't finished and the caller should append the next physical line.
+fn split_record(line: &str, delimiter: char, quote: char) -> Option<Fields> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut in_quotes = false;
+    let mut was_quoted = false;
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == quote {
+                if chars.peek() == Some(&quote) {
+                    chars.next();
+                    field.push(quote);
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                field.push(c);
+            }
+        } else if c == quote && field.is_empty() {
+            in_quotes = true;
+            was_quoted = true;
+        } else if c == delimiter {
+            fields.push((std::mem::take(&mut field), was_quoted));
+            was_quoted = false;
+        } else {
+            field.push(c);
+        }
+    }
+
+    if in_quotes {
+        None
+    } else {
+        fields.push((field, was_quoted));
+        Some(fields)
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{cell_to_string, Cell};
+use std::cmp::Ordering;
+
+/// How two cells in a sort key column should be compared.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SortMode {
+    /// Compare cells by numeric value (`Cell::Integer`/`Cell::Float`, or a
+    /// `Cell::Text` whose text parses as a number; other cells sort as `0`).
+    Numeric,
+    /// Compare the cells' rendered text byte-for-byte.
+    Lexical,
+    /// Compare the cells' rendered text ignoring case (both sides are
+    /// Unicode-lowercased first).
+    CaseInsensitive,
+}
+
+/// A single resolved sort key: the column offset to compare, how to
+/// compare it, and whether the comparison should be reversed.
+pub(crate) struct Compare {
+    pub(crate) col: usize,
+    pub(crate) mode: SortMode,
+    pub(crate) descending: bool,
+}
+
+impl Compare {
+    pub(crate) fn cmp(&self, a: &Cell, b: &Cell) -> Ordering {
+        let ordering = match self.mode {
+            SortMode::Numeric => numeric_value(a)
+                .partial_cmp(&numeric_value(b))
+                .unwrap_or(Ordering::Equal),
+            SortMode::Lexical => cell_to_string(a).cmp(&cell_to_string(b)),
+            SortMode::CaseInsensitive => {
+                cell_to_string(a).to_lowercase().cmp(&cell_to_string(b).to_lowercase())
+            }
+        };
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+fn numeric_value(cell: &Cell) -> f64 {
+    match cell {
+        Cell::Integer(x) => *x as f64,
+        Cell::Float(x) => *x,
+        Cell::Text(x) => x.parse().unwrap_or(0.0),
+        Cell::Empty => 0.0,
+    }
+}
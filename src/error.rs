@@ -0,0 +1,63 @@
+use std::fmt;
+
+/// Errors that can occur while reading or writing a [`crate::Spreadsheet`].
+#[derive(Debug)]
+pub enum SpreadsheetError {
+    /// An underlying I/O error, e.g. the file could not be opened or a
+    /// record's quotes were never closed before the stream ended.
+    Io(std::io::Error),
+    /// The file's bytes were not valid UTF-8.
+    Utf8(std::str::Utf8Error),
+    /// A row had a different number of fields than the header.
+    RaggedRow {
+        /// 1-indexed physical line the row started on.
+        line: usize,
+        /// Number of columns in the header.
+        expected: usize,
+        /// Number of fields actually found in the row.
+        found: usize,
+    },
+}
+
+impl fmt::Display for SpreadsheetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpreadsheetError::Io(e) => write!(f, "I/O error: {}", e),
+            SpreadsheetError::Utf8(e) => write!(f, "invalid UTF-8: {}", e),
+            SpreadsheetError::RaggedRow {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: expected {} fields, found {}",
+                line, expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SpreadsheetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SpreadsheetError::Io(e) => Some(e),
+            SpreadsheetError::Utf8(e) => Some(e),
+            SpreadsheetError::RaggedRow { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SpreadsheetError {
+    fn from(e: std::io::Error) -> Self {
+        SpreadsheetError::Io(e)
+    }
+}
+
+impl From<std::str::Utf8Error> for SpreadsheetError {
+    fn from(e: std::str::Utf8Error) -> Self {
+        SpreadsheetError::Utf8(e)
+    }
+}
+
+/// Convenience alias for the `Result` type used throughout the crate.
+pub type Result<T> = std::result::Result<T, SpreadsheetError>;
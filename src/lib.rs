@@ -1,13 +1,25 @@
 //! Spreadsheet utilities for tab or comma delimited files
 
+mod builder;
+mod compare;
+mod error;
+
 use std::fs::File;
-use std::io::*;
+use std::io::{BufRead, BufWriter, Write};
+
+use compare::Compare;
+
+pub use builder::{ReaderBuilder, Trim};
+pub use compare::SortMode;
+pub use error::{Result, SpreadsheetError};
 
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
 /// Represents a single cell in a spreadsheet
 ///
-/// Three data types are represented: text, floats, and integers
+/// Four data types are represented: empty, text, floats, and integers
 pub enum Cell {
+    /// A blank field
+    Empty,
     /// Text cell
     Text(String),
     /// Floating point number
@@ -17,6 +29,7 @@ pub enum Cell {
 }
 
 /// Spreadsheet struct
+#[derive(Debug)]
 pub struct Spreadsheet {
     /// Vector of cells representing the first row
     /// in the spreadsheet
@@ -29,6 +42,7 @@ pub struct Spreadsheet {
     pub data: Vec<Cell>,
 
     delimiter: char,
+    quote: char,
 }
 
 /// Index into a [`Spreadsheet`] by row and column number
@@ -101,43 +115,56 @@ impl Spreadsheet {
     /// let s = Spreadsheet::read("./test.csv", '\t').unwrap();
     /// ```
     pub fn read(filename: &str, delimiter: char) -> Result<Spreadsheet> {
-        let mut data: Vec<Cell> = Vec::new();
-        let mut headers: Vec<String> = Vec::new();
-        let mut contents = BufReader::new(File::open(filename)?).lines();
+        ReaderBuilder::new(delimiter).from_path(filename)
+    }
 
-        if let Some(Ok(header_line)) = contents.next() {
-            headers.extend(header_line.split(delimiter).map(String::from));
-        }
+    /// Read from any buffered reader, transparently decompressing gzip
+    /// input (sniffed from the `0x1f 0x8b` magic bytes at the start of the
+    /// stream, `gzip` feature only; otherwise gzip input yields a
+    /// [`SpreadsheetError::Io`]) before splitting it into records. Useful for reading from
+    /// `stdin`, an in-memory buffer, or anything else that isn't a plain
+    /// `File`.
+    ///
+    /// The whole stream is read up front and validated as UTF-8, surfacing
+    /// a [`SpreadsheetError::Utf8`] rather than silently losing or
+    /// panicking on invalid encodings. For anything beyond the defaults,
+    /// use [`ReaderBuilder`] directly.
+    pub fn read_with<R: BufRead>(reader: R, delimiter: char) -> Result<Spreadsheet> {
+        ReaderBuilder::new(delimiter).from_reader(reader)
+    }
 
-        let cols = headers.len();
-        let mut rows = 0;
-        while let Some(Ok(cells)) = contents.next() {
-            let mut new_line: Vec<Cell> = Vec::new();
-            for cell in cells.split(delimiter) {
-                if let Ok(x) = cell.parse::<i64>() {
-                    new_line.push(Cell::Integer(x));
-                } else if let Ok(x) = cell.parse::<f64>() {
-                    new_line.push(Cell::Float(x));
-                } else if let Ok(x) = cell.parse::<String>() {
-                    new_line.push(Cell::Text(x));
-                } else {
-                    return Err(Error::from(ErrorKind::UnexpectedEof));
-                }
-            }
-            if new_line.len() != headers.len() {
-                return Err(Error::from(ErrorKind::UnexpectedEof));
-            }
-            rows += 1;
-            data.extend(new_line);
-        }
+    /// Write the spreadsheet out to `filename`, quoting fields per RFC 4180
+    /// where needed (i.e. when a field contains `delimiter`, the quote
+    /// character it was read with, `\r`, or `\n`), with inner quotes
+    /// doubled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use spreadsheet::Spreadsheet;
+    ///
+    /// let s = Spreadsheet::read("./test.csv", '\t').unwrap();
+    /// s.write("./out.csv", '\t').unwrap();
+    /// ```
+    pub fn write(&self, filename: &str, delimiter: char) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(filename)?);
 
-        Ok(Spreadsheet {
-            headers,
-            data,
-            cols,
-            rows,
+        write_record(
+            &mut writer,
+            self.headers.iter().map(String::as_str),
             delimiter,
-        })
+            self.quote,
+        )?;
+        for row in self.iter_rows() {
+            write_record(
+                &mut writer,
+                row.iter().map(cell_to_string),
+                delimiter,
+                self.quote,
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
     }
 
     pub fn iter_rows(&self) -> Row<'_> {
@@ -179,6 +206,61 @@ impl Spreadsheet {
         let idx = self.headers.iter().position(|i| i == col)?;
         Some(self.iter(idx, Direction::Column))
     }
+
+    /// Reorder rows by one or more key columns. Each key is `(header,
+    /// mode, descending)`; ties on an earlier key fall through to the
+    /// next one. The sort is stable, so rows that compare equal on every
+    /// key keep their original relative order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a key names a column that isn't in `headers`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use spreadsheet::{Spreadsheet, SortMode};
+    ///
+    /// let mut s = Spreadsheet::read("./test.csv", '\t').unwrap();
+    /// s.sort_by(&[("name", SortMode::CaseInsensitive, false)]);
+    /// ```
+    pub fn sort_by(&mut self, keys: &[(&str, SortMode, bool)]) {
+        let compares: Vec<Compare> = keys
+            .iter()
+            .map(|(name, mode, descending)| {
+                let col = self
+                    .headers
+                    .iter()
+                    .position(|h| h == name)
+                    .unwrap_or_else(|| panic!("no such column: {}", name));
+                Compare {
+                    col,
+                    mode: *mode,
+                    descending: *descending,
+                }
+            })
+            .collect();
+
+        let mut order: Vec<usize> = (0..self.rows).collect();
+        order.sort_by(|&a, &b| {
+            compares
+                .iter()
+                .map(|cmp| {
+                    cmp.cmp(
+                        &self.data[a * self.cols + cmp.col],
+                        &self.data[b * self.cols + cmp.col],
+                    )
+                })
+                .find(|ordering| *ordering != std::cmp::Ordering::Equal)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let mut sorted = Vec::with_capacity(self.data.len());
+        for row in order {
+            sorted.extend_from_slice(&self.data[row * self.cols..(row + 1) * self.cols]);
+        }
+        self.data = sorted;
+    }
 }
 
 /// An immutable iterator over rows in the [`Spreadsheet`]
@@ -264,36 +346,39 @@ impl<'a> Iterator for Iter<'a> {
     }
 }
 
-// Write the spreadsheet to a tab-separated file, consuming the
-// Spreadsheet in the process
-// pub fn write(self, filename: &str) -> Result<()> {
-//     let mut writer = BufWriter::new(File::create(filename)?);
-
-//     // write the headers first
-//     for cell in self.headers {
-//         writeln!(writer, "{}\t",
-//             match cell {
-//                 Cell::Float(f) => f.to_string(),
-//                 Cell::Integer(x) => x.to_string(),
-//                 Cell::String(x) => x,
-//                 _ => "".into(),
-//             }
-//         )?;
-//     }
-
-//     // iter through each row in the spreadsheet
-//     for row in self.data {
-//         for cell in row {
-//             writeln!(writer, "{}\t",
-//                 match cell {
-//                     Cell::Float(f) => f.to_string(),
-//                     Cell::Integer(x) => x.to_string(),
-//                     Cell::String(x) => x,
-//                     _ => "".into(),
-//                 }
-//             )?;
-//         }
-//     }
-//     // Return an empty unit Ok
-//     Ok(())
-// }
+/// Quote `field` with `quote` if it contains `delimiter`, `quote`, `\r`, or
+/// `\n`, doubling any inner quotes.
+fn quote_field(field: &str, delimiter: char, quote: char) -> String {
+    if field.contains(delimiter) || field.contains([quote, '\r', '\n']) {
+        format!(
+            "{quote}{}{quote}",
+            field.replace(quote, &format!("{quote}{quote}"))
+        )
+    } else {
+        field.to_string()
+    }
+}
+
+pub(crate) fn cell_to_string(cell: &Cell) -> String {
+    match cell {
+        Cell::Empty => String::new(),
+        Cell::Float(f) => f.to_string(),
+        Cell::Integer(x) => x.to_string(),
+        Cell::Text(x) => x.clone(),
+    }
+}
+
+fn write_record<W, I, S>(writer: &mut W, fields: I, delimiter: char, quote: char) -> Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let line = fields
+        .into_iter()
+        .map(|field| quote_field(field.as_ref(), delimiter, quote))
+        .collect::<Vec<_>>()
+        .join(&delimiter.to_string());
+    writeln!(writer, "{}", line)?;
+    Ok(())
+}
@@ -70,3 +70,187 @@ fn index() {
     let spread = Spreadsheet::read("test.csv", ',').unwrap();
     assert_eq!(spread[SymbolicIndex::new(0, "x")], Cell::Integer(0));
 }
+
+#[test]
+fn quoted_fields() {
+    let spread = Spreadsheet::read("quoted.csv", ',').unwrap();
+    let rows = spread
+        .iter_rows()
+        .map(Vec::from)
+        .collect::<Vec<Vec<Cell>>>();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(
+        rows[0],
+        vec![
+            Cell::Text("hello, world".into()),
+            Cell::Text("a \"quoted\" word".into()),
+            Cell::Integer(1),
+        ]
+    );
+    assert_eq!(
+        rows[1],
+        vec![
+            Cell::Text("line one\nline two".into()),
+            Cell::Text("plain".into()),
+            Cell::Integer(2),
+        ]
+    );
+}
+
+#[test]
+fn write_roundtrip() {
+    let spread = Spreadsheet::read("quoted.csv", ',').unwrap();
+    spread.write("quoted_roundtrip.csv", ',').unwrap();
+    let reread = Spreadsheet::read("quoted_roundtrip.csv", ',').unwrap();
+    assert_eq!(spread.data, reread.data);
+    assert_eq!(spread.headers, reread.headers);
+    std::fs::remove_file("quoted_roundtrip.csv").unwrap();
+}
+
+#[test]
+fn empty_cell() {
+    let spread = Spreadsheet::read("empty_cells.csv", ',').unwrap();
+    let rows = spread
+        .iter_rows()
+        .map(Vec::from)
+        .collect::<Vec<Vec<Cell>>>();
+    assert_eq!(
+        rows[0],
+        vec![Cell::Integer(1), Cell::Empty, Cell::Integer(3)]
+    );
+    assert_eq!(
+        rows[1],
+        vec![Cell::Empty, Cell::Integer(5), Cell::Empty]
+    );
+}
+
+#[test]
+fn ragged_row_error() {
+    std::fs::write("ragged.csv", "a,b,c\n1,2,3\n4,5\n").unwrap();
+    let err = Spreadsheet::read("ragged.csv", ',').unwrap_err();
+    std::fs::remove_file("ragged.csv").unwrap();
+    match err {
+        SpreadsheetError::RaggedRow {
+            line,
+            expected,
+            found,
+        } => {
+            assert_eq!(line, 3);
+            assert_eq!(expected, 3);
+            assert_eq!(found, 2);
+        }
+        other => panic!("expected RaggedRow, got {:?}", other),
+    }
+}
+
+#[test]
+fn sort_by_numeric_key() {
+    let mut spread = Spreadsheet::read("test.csv", ',').unwrap();
+    spread.sort_by(&[("x", SortMode::Numeric, true)]);
+    let col0 = spread.column("x").unwrap().collect::<Vec<_>>();
+    assert_eq!(
+        col0,
+        vec![
+            &Cell::Integer(9),
+            &Cell::Integer(6),
+            &Cell::Integer(3),
+            &Cell::Integer(0),
+        ]
+    );
+}
+
+#[test]
+fn sort_by_multiple_keys_breaks_ties_with_next_key() {
+    std::fs::write(
+        "sort_keys.csv",
+        "group,name\nb,two\na,one\nb,one\na,two\n",
+    )
+    .unwrap();
+    let mut spread = Spreadsheet::read("sort_keys.csv", ',').unwrap();
+    std::fs::remove_file("sort_keys.csv").unwrap();
+
+    spread.sort_by(&[
+        ("group", SortMode::Lexical, false),
+        ("name", SortMode::Lexical, false),
+    ]);
+
+    let rows = spread
+        .iter_rows()
+        .map(Vec::from)
+        .collect::<Vec<Vec<Cell>>>();
+    assert_eq!(
+        rows,
+        vec![
+            vec![Cell::Text("a".into()), Cell::Text("one".into())],
+            vec![Cell::Text("a".into()), Cell::Text("two".into())],
+            vec![Cell::Text("b".into()), Cell::Text("one".into())],
+            vec![Cell::Text("b".into()), Cell::Text("two".into())],
+        ]
+    );
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn read_gzip() {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    let plain = Spreadsheet::read("test.csv", ',').unwrap();
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&std::fs::read("test.csv").unwrap())
+        .unwrap();
+    let gzipped = encoder.finish().unwrap();
+
+    let spread = Spreadsheet::read_with(std::io::BufReader::new(gzipped.as_slice()), ',').unwrap();
+    assert_eq!(spread.data, plain.data);
+    assert_eq!(spread.headers, plain.headers);
+}
+
+#[test]
+fn builder_headerless_synthesizes_columns() {
+    let spread = ReaderBuilder::new(',')
+        .has_headers(false)
+        .from_path("headerless.csv")
+        .unwrap();
+    assert_eq!(spread.headers, vec!["col0", "col1", "col2"]);
+    assert_eq!(spread.rows, 2);
+    assert_eq!(spread.data[0], Cell::Integer(1));
+}
+
+#[test]
+fn builder_flexible_pads_and_truncates_rows() {
+    let spread = ReaderBuilder::new(',')
+        .flexible(true)
+        .from_path("flexible.csv")
+        .unwrap();
+    let rows = spread
+        .iter_rows()
+        .map(Vec::from)
+        .collect::<Vec<Vec<Cell>>>();
+    assert_eq!(
+        rows[1],
+        vec![Cell::Integer(4), Cell::Integer(5), Cell::Empty]
+    );
+    assert_eq!(
+        rows[2],
+        vec![Cell::Integer(6), Cell::Integer(7), Cell::Integer(8)]
+    );
+}
+
+#[test]
+fn builder_trim_and_null_tokens() {
+    let spread = ReaderBuilder::new(',')
+        .trim(Trim::All)
+        .null_token("NA")
+        .from_path("padded.csv")
+        .unwrap();
+    assert_eq!(spread.headers, vec!["a", "b"]);
+    let rows = spread
+        .iter_rows()
+        .map(Vec::from)
+        .collect::<Vec<Vec<Cell>>>();
+    assert_eq!(rows[0], vec![Cell::Integer(1), Cell::Empty]);
+}